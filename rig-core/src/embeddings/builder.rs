@@ -63,19 +63,43 @@
 //! // ...
 //! ```
 
-use std::{cmp::max, collections::HashMap};
+use std::{cmp::max, collections::HashMap, ops::Range, sync::Arc};
 
-use futures::{stream, StreamExt, TryStreamExt};
+use futures::{stream, Stream, StreamExt, TryStreamExt};
 
 use crate::{
-    embeddings::{Embeddable, Embedding, EmbeddingError, EmbeddingModel},
+    embeddings::{
+        retry::RetryPolicy, text_splitter::TextSplitter, Embeddable, Embedding, EmbeddingError,
+        EmbeddingModel, Embeddings,
+    },
     OneOrMany,
 };
 
+/// Token budget a single embed target is allowed to occupy before
+/// [`EmbeddingsBuilder`] splits it into multiple chunks. Generous enough to leave most
+/// short document fields untouched, while still protecting large ones from overflowing a
+/// model's context window.
+const DEFAULT_CHUNK_SIZE: usize = 2048;
+
+/// A chunk whose embedding request failed even after exhausting the builder's
+/// [`RetryPolicy`], returned by [`EmbeddingsBuilder::build_partial`] instead of aborting
+/// the whole build.
+#[derive(Clone, Debug)]
+pub struct FailedChunk {
+    pub text: String,
+    pub range: Range<usize>,
+    pub error: String,
+}
+
 /// Builder for creating a collection of embeddings.
 pub struct EmbeddingsBuilder<M: EmbeddingModel, D: Embeddable> {
     model: M,
-    documents: Vec<(D, OneOrMany<String>)>,
+    documents: Vec<(D, Vec<(String, Range<usize>)>)>,
+    precomputed: Vec<(D, OneOrMany<Embedding>)>,
+    chunk_size: usize,
+    chunk_overlap: usize,
+    max_concurrent: Option<usize>,
+    retry_policy: RetryPolicy,
 }
 
 impl<M: EmbeddingModel, D: Embeddable> EmbeddingsBuilder<M, D> {
@@ -84,14 +108,56 @@ impl<M: EmbeddingModel, D: Embeddable> EmbeddingsBuilder<M, D> {
         Self {
             model,
             documents: vec![],
+            precomputed: vec![],
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            chunk_overlap: 0,
+            max_concurrent: None,
+            retry_policy: RetryPolicy::default(),
         }
     }
 
+    /// Cap the number of embedding requests in flight at once. Defaults to
+    /// `max(1, 1024 / M::MAX_DOCUMENTS)`, which assumes a provider-wide budget of ~1024
+    /// documents in flight; lower this for providers with tighter rate limits.
+    ///
+    /// Clamped to at least `1`: `buffer_unordered(0)` never polls its underlying stream, so
+    /// a `0` here would hang `build`/`build_partial`/`build_stream` forever instead of
+    /// running sequentially.
+    pub fn max_concurrent(mut self, n: usize) -> Self {
+        self.max_concurrent = Some(n.max(1));
+        self
+    }
+
+    /// Retry a chunk's embedding request with exponential backoff when it fails with a
+    /// transient/rate-limit error. Defaults to [`RetryPolicy::default`], which does not
+    /// retry.
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Set the maximum number of (estimated) tokens an embed target is allowed to occupy
+    /// before it gets split into multiple chunks. Defaults to [`DEFAULT_CHUNK_SIZE`].
+    pub fn chunk_size(mut self, max_tokens: usize) -> Self {
+        self.chunk_size = max_tokens;
+        self
+    }
+
+    /// Re-seed each chunk (after the first) of a split embed target with the trailing
+    /// `overlap_tokens` tokens of the previous chunk, so a downstream reader doesn't lose
+    /// context at the boundary. Defaults to `0`. The overlap is left out of a chunk whose
+    /// first piece would not fit in the chunk size alongside it.
+    pub fn chunk_overlap(mut self, overlap_tokens: usize) -> Self {
+        self.chunk_overlap = overlap_tokens;
+        self
+    }
+
     /// Add a document that implements `Embeddable` to the builder.
     pub fn document(mut self, document: D) -> Result<Self, D::Error> {
         let embed_targets = document.embeddable()?;
+        let chunks = self.chunk_targets(embed_targets);
 
-        self.documents.push((document, embed_targets));
+        self.documents.push((document, chunks));
         Ok(self)
     }
 
@@ -99,19 +165,276 @@ impl<M: EmbeddingModel, D: Embeddable> EmbeddingsBuilder<M, D> {
     pub fn documents(mut self, documents: Vec<D>) -> Result<Self, D::Error> {
         for doc in documents.into_iter() {
             let embed_targets = doc.embeddable()?;
+            let chunks = self.chunk_targets(embed_targets);
 
-            self.documents.push((doc, embed_targets));
+            self.documents.push((doc, chunks));
         }
 
         Ok(self)
     }
+
+    /// Add a document that already carries its embeddings, e.g. loaded from a previous
+    /// indexing run, so it doesn't get re-embedded on every `build()`.
+    ///
+    /// When `regenerate` is `false` the given embeddings are kept as-is and passed through
+    /// `build()` untouched. When `true` the document is queued for embedding exactly like
+    /// [`Self::document`] and `embeddings` is discarded — useful when the caller always
+    /// supplies the last-known embeddings but only some documents actually changed.
+    pub fn document_with_embeddings(
+        mut self,
+        document: D,
+        embeddings: OneOrMany<Embedding>,
+        regenerate: bool,
+    ) -> Result<Self, D::Error> {
+        if regenerate {
+            return self.document(document);
+        }
+
+        self.precomputed.push((document, embeddings));
+        Ok(self)
+    }
+
+    /// Split each embed target into token-bounded chunks, recording the source byte range
+    /// each chunk came from.
+    fn chunk_targets(&self, targets: OneOrMany<String>) -> Vec<(String, Range<usize>)> {
+        let splitter = TextSplitter::new(self.chunk_size).with_overlap(self.chunk_overlap);
+
+        targets
+            .into_iter()
+            .flat_map(|target| {
+                splitter
+                    .split(&target)
+                    .into_iter()
+                    .map(|chunk| (chunk.text, chunk.range))
+            })
+            .collect()
+    }
 }
 
 impl<M: EmbeddingModel, D: Embeddable + Send + Sync + Clone> EmbeddingsBuilder<M, D> {
     /// Generate embeddings for all documents in the builder.
     /// The method only applies when documents in the builder each contain multiple embedding targets.
     /// Returns a vector of tuples, where the first element is the document and the second element is the vector of embeddings.
-    pub async fn build(&self) -> Result<Vec<(D, OneOrMany<Embedding>)>, EmbeddingError> {
+    ///
+    /// Aborts and returns the first error encountered (after exhausting the builder's
+    /// [`RetryPolicy`]). Use [`Self::build_partial`] to keep the results that did succeed
+    /// instead.
+    pub async fn build(&self) -> Result<Vec<(D, Embeddings)>, EmbeddingError> {
+        let (built, failed) = self.run(false).await?;
+        debug_assert!(failed.is_empty(), "build() never collects partial failures");
+        Ok(built)
+    }
+
+    /// Like [`Self::build`], but a chunk that still fails after exhausting the
+    /// [`RetryPolicy`] is recorded in the returned `Vec<FailedChunk>` instead of aborting
+    /// the whole build — useful for large indexing jobs where losing all prior work to one
+    /// rate-limited chunk is worse than indexing everything else and retrying the gap
+    /// later.
+    pub async fn build_partial(&self) -> Result<(Vec<(D, Embeddings)>, Vec<FailedChunk>), EmbeddingError> {
+        self.run(true).await
+    }
+
+    /// Like [`Self::build`], but yields each document's embeddings as soon as all of its
+    /// chunks have come back, instead of buffering every document into memory and
+    /// returning a single `Vec` at the end. Lets a caller pipe results directly into a
+    /// vector store (and report progress) while indexing a large corpus, rather than
+    /// waiting for the whole batch to finish. Precomputed/non-regenerated documents are
+    /// emitted first, since they require no embedding calls at all.
+    pub fn build_stream(&self) -> impl Stream<Item = Result<(D, Embeddings), EmbeddingError>> + '_ {
+        let dimension = self.model.ndims();
+        let concurrency = self
+            .max_concurrent
+            .unwrap_or_else(|| max(1, 1024 / M::MAX_DOCUMENTS));
+
+        let documents_map = Arc::new(
+            self.documents
+                .iter()
+                .enumerate()
+                .map(|(id, (document, _))| (id, document.clone()))
+                .collect::<HashMap<_, _>>(),
+        );
+        let remaining = self
+            .documents
+            .iter()
+            .enumerate()
+            .map(|(id, (_, chunks))| (id, chunks.len()))
+            .collect::<HashMap<_, _>>();
+
+        let precomputed = stream::iter(
+            self.precomputed
+                .iter()
+                .cloned()
+                .map(move |(document, embeddings)| {
+                    Self::flatten_precomputed(document, embeddings, dimension)
+                }),
+        );
+
+        let embedded = stream::iter(self.documents.iter().enumerate())
+            .flat_map(|(i, (_, chunks))| {
+                stream::iter(chunks.clone().into_iter().map(move |chunk| (i, chunk)))
+            })
+            .chunks(M::MAX_DOCUMENTS)
+            .map(move |docs| async move {
+                let (document_indices, chunks): (Vec<_>, Vec<(String, Range<usize>)>) =
+                    docs.into_iter().unzip();
+                let (texts, ranges): (Vec<_>, Vec<_>) = chunks.into_iter().unzip();
+
+                let embedded = async {
+                    let embeddings = self.embed_chunk(texts).await?;
+
+                    document_indices
+                        .iter()
+                        .copied()
+                        .zip(ranges)
+                        .zip(embeddings)
+                        .map(|((i, range), embedding)| {
+                            if embedding.vec.len() != dimension {
+                                return Err(EmbeddingError::DocumentError(
+                                    format!(
+                                        "model returned embedding of dimension {}, expected {dimension}",
+                                        embedding.vec.len(),
+                                    )
+                                    .into(),
+                                ));
+                            }
+
+                            Ok((i, range, embedding))
+                        })
+                        .collect::<Result<Vec<_>, _>>()
+                }
+                .await;
+
+                // Keep `document_indices` attached to a failure, so the scan stage below can
+                // still flush an error for every document that was in this batch instead of
+                // silently stranding them.
+                embedded.map_err(|err| (document_indices, err))
+            })
+            .boxed()
+            .buffer_unordered(concurrency)
+            .scan(
+                (HashMap::<usize, (Vec<f32>, Vec<Range<usize>>)>::new(), remaining),
+                move |(acc, remaining), result| {
+                    let documents_map = documents_map.clone();
+
+                    async move {
+                        let mut done = Vec::new();
+
+                        match result {
+                            Err((document_indices, err)) => {
+                                for i in document_indices {
+                                    // This document's batch failed: stop waiting on it (any
+                                    // chunks that complete for it later are ignored, matching
+                                    // its already-failed status) and report the failure once.
+                                    // A document no longer in `remaining` was already reported.
+                                    if remaining.remove(&i).is_none() {
+                                        continue;
+                                    }
+                                    acc.remove(&i);
+                                    done.push(Err(EmbeddingError::DocumentError(
+                                        format!("chunk embedding failed: {err}").into(),
+                                    )));
+                                }
+                            }
+                            Ok(items) => {
+                                let mut touched = Vec::new();
+                                for (i, range, embedding) in items {
+                                    let (data, ranges) = acc.entry(i).or_default();
+                                    data.extend(embedding.vec.iter().map(|x| *x as f32));
+                                    ranges.push(range);
+                                    touched.push(i);
+                                }
+
+                                for i in touched {
+                                    let Some(count) = remaining.get_mut(&i) else {
+                                        continue;
+                                    };
+                                    *count -= 1;
+                                    if *count == 0 {
+                                        let (data, ranges) = acc.remove(&i).unwrap();
+                                        done.push(
+                                            Embeddings::from_inner(data, dimension, ranges).map(
+                                                |embeddings| {
+                                                    (documents_map.get(&i).cloned().unwrap(), embeddings)
+                                                },
+                                            ),
+                                        );
+                                    }
+                                }
+                            }
+                        }
+
+                        Some(done)
+                    }
+                },
+            )
+            .flat_map(stream::iter);
+
+        precomputed.chain(embedded)
+    }
+
+    /// Flatten a precomputed document's embeddings into an [`Embeddings`] buffer,
+    /// rejecting any individual embedding whose dimension doesn't match the model's —
+    /// otherwise a wrong-dimension vector would silently shift every later vector's rows
+    /// once flattened.
+    fn flatten_precomputed(
+        document: D,
+        embeddings: OneOrMany<Embedding>,
+        dimension: usize,
+    ) -> Result<(D, Embeddings), EmbeddingError> {
+        let mut data = Vec::with_capacity(embeddings.len() * dimension);
+        let mut ranges = Vec::with_capacity(embeddings.len());
+
+        for embedding in embeddings.iter() {
+            if embedding.vec.len() != dimension {
+                return Err(EmbeddingError::DocumentError(
+                    format!(
+                        "precomputed embedding has dimension {}, expected {dimension}",
+                        embedding.vec.len(),
+                    )
+                    .into(),
+                ));
+            }
+
+            data.extend(embedding.vec.iter().map(|x| *x as f32));
+            ranges.push(0..embedding.document.len());
+        }
+
+        let embeddings = Embeddings::from_inner(data, dimension, ranges)
+            .expect("data is laid out in `dimension`-sized rows by construction above");
+
+        Ok((document, embeddings))
+    }
+
+    /// Embed a single chunk, retrying on transient errors per `self.retry_policy`.
+    async fn embed_chunk(&self, texts: Vec<String>) -> Result<Vec<Embedding>, EmbeddingError> {
+        let mut attempt = 0;
+        loop {
+            match self.model.embed_documents(texts.clone()).await {
+                Ok(embeddings) => return Ok(embeddings),
+                Err(err) if attempt < self.retry_policy.max_retries() && self.retry_policy.is_retryable(&err) => {
+                    tokio::time::sleep(self.retry_policy.backoff_for(attempt)).await;
+                    attempt += 1;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    async fn run(
+        &self,
+        collect_partial: bool,
+    ) -> Result<(Vec<(D, Embeddings)>, Vec<FailedChunk>), EmbeddingError> {
+        let dimension = self.model.ndims();
+
+        // Validate and flatten up front so a bad precomputed embedding is reported before
+        // we pay for any (potentially expensive) embedding calls below.
+        let precomputed = self
+            .precomputed
+            .iter()
+            .cloned()
+            .map(|(document, embeddings)| Self::flatten_precomputed(document, embeddings, dimension))
+            .collect::<Result<Vec<_>, _>>()?;
+
         // Use this for reference later to merge a document back with its embeddings.
         let documents_map = self
             .documents
@@ -121,54 +444,425 @@ impl<M: EmbeddingModel, D: Embeddable + Send + Sync + Clone> EmbeddingsBuilder<M
             .map(|(id, (document, _))| (id, document))
             .collect::<HashMap<_, _>>();
 
-        let embeddings = stream::iter(self.documents.iter().enumerate())
-            // Merge the embedding targets of each document into a single list of embedding targets.
-            .flat_map(|(i, (_, embed_targets))| {
-                stream::iter(
-                    embed_targets
-                        .clone()
-                        .into_iter()
-                        .map(move |target| (i, target)),
-                )
+        let concurrency = self
+            .max_concurrent
+            .unwrap_or_else(|| max(1, 1024 / M::MAX_DOCUMENTS));
+
+        let (by_document, failed_chunks) = stream::iter(self.documents.iter().enumerate())
+            // Merge the embedding chunks of each document into a single list of embedding targets.
+            .flat_map(|(i, (_, chunks))| {
+                stream::iter(chunks.clone().into_iter().map(move |chunk| (i, chunk)))
             })
             // Chunk them into N (the emebdding API limit per request).
             .chunks(M::MAX_DOCUMENTS)
-            // Generate the embeddings for a chunk at a time.
+            // Generate the embeddings for a chunk at a time, retrying transient failures.
             .map(|docs| async {
-                let (document_indices, embed_targets): (Vec<_>, Vec<_>) = docs.into_iter().unzip();
+                let (document_indices, chunks): (Vec<_>, Vec<(String, Range<usize>)>) =
+                    docs.into_iter().unzip();
+                let (texts, ranges): (Vec<_>, Vec<_>) = chunks.into_iter().unzip();
 
-                Ok::<_, EmbeddingError>(
-                    document_indices
-                        .into_iter()
-                        .zip(self.model.embed_documents(embed_targets).await?.into_iter())
-                        .collect::<Vec<_>>(),
-                )
+                let embeddings = match self.embed_chunk(texts.clone()).await {
+                    Ok(embeddings) => embeddings,
+                    Err(err) if collect_partial => {
+                        let failed = texts
+                            .into_iter()
+                            .zip(ranges)
+                            .map(|(text, range)| FailedChunk {
+                                text,
+                                range,
+                                error: err.to_string(),
+                            })
+                            .collect::<Vec<_>>();
+
+                        return Ok::<_, EmbeddingError>((vec![], failed));
+                    }
+                    Err(err) => return Err(err),
+                };
+
+                let embedded = document_indices
+                    .into_iter()
+                    .zip(ranges)
+                    .zip(embeddings)
+                    .map(|((i, range), embedding)| {
+                        if embedding.vec.len() != dimension {
+                            return Err(EmbeddingError::DocumentError(
+                                format!(
+                                    "model returned embedding of dimension {}, expected {dimension}",
+                                    embedding.vec.len(),
+                                )
+                                .into(),
+                            ));
+                        }
+
+                        Ok((i, range, embedding))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                Ok((embedded, vec![]))
             })
             .boxed()
-            // Parallelize the embeddings generation over 10 concurrent requests
-            .buffer_unordered(max(1, 1024 / M::MAX_DOCUMENTS))
+            // Parallelize the embeddings generation over `concurrency` concurrent requests.
+            .buffer_unordered(concurrency)
             .try_fold(
-                HashMap::new(),
-                |mut acc: HashMap<_, OneOrMany<Embedding>>, embeddings| async move {
-                    embeddings.into_iter().for_each(|(i, embedding)| {
-                        acc.entry(i)
-                            .or_insert(OneOrMany::one(embedding.clone()))
-                            .add(embedding.clone());
+                (HashMap::new(), Vec::new()),
+                |(mut acc, mut failed): (HashMap<_, (Vec<f32>, Vec<Range<usize>>)>, Vec<FailedChunk>),
+                 (embedded, mut chunk_failed)| async move {
+                    embedded.into_iter().for_each(|(i, range, embedding)| {
+                        let (data, ranges) = acc.entry(i).or_default();
+                        data.extend(embedding.vec.iter().map(|x| *x as f32));
+                        ranges.push(range);
                     });
+                    failed.append(&mut chunk_failed);
 
-                    Ok(acc)
+                    Ok((acc, failed))
                 },
             )
-            .await?
+            .await?;
+
+        let embeddings = by_document
+            .into_iter()
+            .map(|(i, (data, ranges))| {
+                let embeddings = Embeddings::from_inner(data, dimension, ranges)
+                    .expect("data is laid out in `dimension`-sized rows by construction above");
+
+                (documents_map.get(&i).cloned().unwrap(), embeddings)
+            })
+            .collect::<Vec<_>>();
+
+        // Merge back in the documents that already had their embeddings computed, so
+        // callers only pay the embedding cost for what actually changed.
+        Ok((
+            embeddings.into_iter().chain(precomputed).collect(),
+            failed_chunks,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    use super::*;
+
+    #[derive(Clone)]
+    struct MockDoc {
+        id: String,
+        text: String,
+    }
+
+    impl Embeddable for MockDoc {
+        type Error = std::convert::Infallible;
+
+        fn embeddable(&self) -> Result<OneOrMany<String>, Self::Error> {
+            Ok(OneOrMany::one(self.text.clone()))
+        }
+    }
+
+    /// A model that returns a fixed-dimension embedding for every text, recording how many
+    /// times it was called so tests can assert on call counts (e.g. that a precomputed
+    /// embedding never triggers one). Optionally fails the first `fail_first_n` calls (to
+    /// exercise retry/backoff) and/or tracks how many calls are in flight at once (to
+    /// exercise `max_concurrent`).
+    #[derive(Clone)]
+    struct MockModel {
+        dimension: usize,
+        calls: Arc<AtomicUsize>,
+        fail_first_n: usize,
+        in_flight: Option<Arc<AtomicUsize>>,
+        max_observed: Option<Arc<AtomicUsize>>,
+    }
+
+    impl MockModel {
+        fn new(dimension: usize) -> Self {
+            Self {
+                dimension,
+                calls: Arc::new(AtomicUsize::new(0)),
+                fail_first_n: 0,
+                in_flight: None,
+                max_observed: None,
+            }
+        }
+
+        /// Like [`Self::new`], but the first `fail_first_n` calls return a retryable
+        /// `EmbeddingError::ProviderError` instead of succeeding.
+        fn failing(dimension: usize, fail_first_n: usize) -> Self {
+            Self {
+                fail_first_n,
+                ..Self::new(dimension)
+            }
+        }
+
+        /// Record the number of calls in flight at once into `max_observed`.
+        fn with_concurrency_tracking(
+            mut self,
+            in_flight: Arc<AtomicUsize>,
+            max_observed: Arc<AtomicUsize>,
+        ) -> Self {
+            self.in_flight = Some(in_flight);
+            self.max_observed = Some(max_observed);
+            self
+        }
+    }
+
+    impl EmbeddingModel for MockModel {
+        const MAX_DOCUMENTS: usize = 2;
+
+        fn ndims(&self) -> usize {
+            self.dimension
+        }
+
+        async fn embed_documents(
+            &self,
+            documents: Vec<String>,
+        ) -> Result<Vec<Embedding>, EmbeddingError> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst);
+
+            if let (Some(in_flight), Some(max_observed)) = (&self.in_flight, &self.max_observed) {
+                let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                max_observed.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(5)).await;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+            }
+
+            if call < self.fail_first_n {
+                return Err(EmbeddingError::ProviderError("rate limited".to_string()));
+            }
+
+            Ok(documents
+                .into_iter()
+                .map(|document| Embedding {
+                    vec: vec![1.0; self.dimension],
+                    document,
+                })
+                .collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn precomputed_embeddings_are_passed_through_without_an_embed_call() {
+        let model = MockModel::new(3);
+        let doc = MockDoc {
+            id: "1".to_string(),
+            text: "hello".to_string(),
+        };
+        let embeddings = OneOrMany::one(Embedding {
+            document: "hello".to_string(),
+            vec: vec![0.1, 0.2, 0.3],
+        });
+
+        let (results, failed) = EmbeddingsBuilder::new(model.clone())
+            .document_with_embeddings(doc, embeddings, false)
+            .unwrap()
+            .build_partial()
+            .await
+            .unwrap();
+
+        assert!(failed.is_empty());
+        assert_eq!(results.len(), 1);
+        assert_eq!(
+            model.calls.load(Ordering::SeqCst),
+            0,
+            "a precomputed embedding must not trigger an embed call"
+        );
+    }
+
+    #[tokio::test]
+    async fn regenerate_flag_re_embeds_instead_of_reusing_the_precomputed_embedding() {
+        let model = MockModel::new(3);
+        let doc = MockDoc {
+            id: "1".to_string(),
+            text: "hello".to_string(),
+        };
+        let stale = OneOrMany::one(Embedding {
+            document: "stale".to_string(),
+            vec: vec![0.1, 0.2, 0.3],
+        });
+
+        let (results, _) = EmbeddingsBuilder::new(model.clone())
+            .document_with_embeddings(doc, stale, true)
+            .unwrap()
+            .build_partial()
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(model.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn mismatched_precomputed_dimension_is_rejected() {
+        let model = MockModel::new(3);
+        let doc = MockDoc {
+            id: "1".to_string(),
+            text: "hello".to_string(),
+        };
+        let wrong_dimension = OneOrMany::one(Embedding {
+            document: "hello".to_string(),
+            vec: vec![0.1, 0.2],
+        });
+
+        let result = EmbeddingsBuilder::new(model)
+            .document_with_embeddings(doc, wrong_dimension, false)
+            .unwrap()
+            .build()
+            .await;
+
+        assert!(matches!(result, Err(EmbeddingError::DocumentError(_))));
+    }
+
+    #[tokio::test]
+    async fn transient_failure_is_retried_until_it_succeeds() {
+        let model = MockModel::failing(3, 2);
+        let doc = MockDoc {
+            id: "1".to_string(),
+            text: "hello".to_string(),
+        };
+
+        let results = EmbeddingsBuilder::new(model.clone())
+            .retry_policy(RetryPolicy::new(2).initial_backoff(Duration::from_millis(1)))
+            .document(doc)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(model.calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn build_aborts_once_retries_are_exhausted() {
+        let model = MockModel::failing(3, 10);
+        let doc = MockDoc {
+            id: "1".to_string(),
+            text: "hello".to_string(),
+        };
+
+        let result = EmbeddingsBuilder::new(model)
+            .retry_policy(RetryPolicy::new(1).initial_backoff(Duration::from_millis(1)))
+            .document(doc)
+            .unwrap()
+            .build()
+            .await;
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn build_partial_collects_the_chunk_as_failed_instead_of_aborting() {
+        let model = MockModel::failing(3, 10);
+        let doc = MockDoc {
+            id: "1".to_string(),
+            text: "hello".to_string(),
+        };
+
+        let (results, failed) = EmbeddingsBuilder::new(model)
+            .document(doc)
+            .unwrap()
+            .build_partial()
+            .await
+            .unwrap();
+
+        assert!(results.is_empty());
+        assert_eq!(failed.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn max_concurrent_caps_the_number_of_in_flight_embed_calls() {
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let max_observed = Arc::new(AtomicUsize::new(0));
+        let model = MockModel::new(3).with_concurrency_tracking(in_flight, max_observed.clone());
+
+        let mut builder = EmbeddingsBuilder::new(model).max_concurrent(1);
+        for i in 0..6 {
+            builder = builder
+                .document(MockDoc {
+                    id: i.to_string(),
+                    text: format!("doc {i}"),
+                })
+                .unwrap();
+        }
+
+        builder.build().await.unwrap();
+
+        assert_eq!(max_observed.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn build_stream_yields_each_document_once_all_its_chunks_complete() {
+        let model = MockModel::new(3);
+        let mut builder = EmbeddingsBuilder::new(model);
+        for i in 0..3 {
+            builder = builder
+                .document(MockDoc {
+                    id: i.to_string(),
+                    text: format!("doc {i}"),
+                })
+                .unwrap();
+        }
+
+        let results = builder.build_stream().collect::<Vec<_>>().await;
+        let mut ids: Vec<_> = results.into_iter().map(|r| r.unwrap().0.id).collect();
+        ids.sort();
+
+        assert_eq!(ids, vec!["0".to_string(), "1".to_string(), "2".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn build_stream_emits_precomputed_documents_without_an_embed_call() {
+        let model = MockModel::new(3);
+        let doc = MockDoc {
+            id: "pre".to_string(),
+            text: "hello".to_string(),
+        };
+        let embeddings = OneOrMany::one(Embedding {
+            document: "hello".to_string(),
+            vec: vec![0.1, 0.2, 0.3],
+        });
+
+        let builder = EmbeddingsBuilder::new(model.clone())
+            .document_with_embeddings(doc, embeddings, false)
+            .unwrap();
+
+        let results = builder.build_stream().collect::<Vec<_>>().await;
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(model.calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn build_stream_reports_every_document_in_a_failed_batch() {
+        // MAX_DOCUMENTS == 2, so with 3 single-chunk documents the first batch holds docs
+        // 0 and 1 and the second holds doc 2. Failing only the first call fails that first
+        // batch while letting the second succeed.
+        let model = MockModel::failing(3, 1);
+        let mut builder = EmbeddingsBuilder::new(model);
+        for i in 0..3 {
+            builder = builder
+                .document(MockDoc {
+                    id: i.to_string(),
+                    text: format!("doc {i}"),
+                })
+                .unwrap();
+        }
+
+        let results = builder.build_stream().collect::<Vec<_>>().await;
+
+        assert_eq!(
+            results.len(),
+            3,
+            "every document must be accounted for, not silently dropped"
+        );
+
+        let errors = results.iter().filter(|r| r.is_err()).count();
+        let succeeded: Vec<_> = results
             .iter()
-            .fold(vec![], |mut acc, (i, embeddings_vec)| {
-                acc.push((
-                    documents_map.get(i).cloned().unwrap(),
-                    embeddings_vec.clone(),
-                ));
-                acc
-            });
+            .filter_map(|r| r.as_ref().ok())
+            .map(|(doc, _)| doc.id.clone())
+            .collect();
 
-        Ok(embeddings)
+        assert_eq!(errors, 2);
+        assert_eq!(succeeded, vec!["2".to_string()]);
     }
 }
@@ -0,0 +1,136 @@
+//! Flat, contiguous storage for the embeddings of a single document.
+//!
+//! [`EmbeddingsBuilder::build`](crate::embeddings::EmbeddingsBuilder::build) previously
+//! returned `OneOrMany<Embedding>`, which allocates one vector per chunk. For
+//! documents with many embed targets that means one heap allocation (and poor cache
+//! locality) per chunk. [`Embeddings`] instead stores every vector for a document in a
+//! single flat buffer, which keeps bulk operations like normalized dot-product comparisons
+//! fast.
+
+use std::ops::Range;
+
+use crate::embeddings::EmbeddingError;
+
+/// All the embedding vectors generated for a single document, stored contiguously.
+///
+/// Each vector occupies `dimension` consecutive `f32`s in `data`; the chunk's source byte
+/// range (in the original embed target) is kept alongside it so a vector store can still
+/// map a hit back to the exact span it came from.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Embeddings {
+    data: Vec<f32>,
+    dimension: usize,
+    ranges: Vec<Range<usize>>,
+}
+
+impl Embeddings {
+    /// Build an `Embeddings` holding a single vector.
+    ///
+    /// `vec` must be non-empty: an empty vector would leave `dimension` at `0`, which
+    /// [`embedding_count`](Self::embedding_count) and [`iter`](Self::iter) can't divide
+    /// by.
+    pub fn from_single(vec: Vec<f32>, range: Range<usize>) -> Result<Self, EmbeddingError> {
+        if vec.is_empty() {
+            return Err(EmbeddingError::DocumentError(
+                "embedding vector must not be empty".into(),
+            ));
+        }
+
+        let dimension = vec.len();
+        Ok(Self {
+            data: vec,
+            dimension,
+            ranges: vec![range],
+        })
+    }
+
+    /// Build an `Embeddings` from an already-flattened buffer, erroring unless `data` is an
+    /// exact multiple of `dimension` (one range per vector).
+    pub fn from_inner(
+        data: Vec<f32>,
+        dimension: usize,
+        ranges: Vec<Range<usize>>,
+    ) -> Result<Self, EmbeddingError> {
+        if dimension == 0 || data.len() % dimension != 0 {
+            return Err(EmbeddingError::DocumentError(
+                format!(
+                    "embeddings data of length {} is not a multiple of dimension {dimension}",
+                    data.len()
+                )
+                .into(),
+            ));
+        }
+
+        if data.len() / dimension != ranges.len() {
+            return Err(EmbeddingError::DocumentError(
+                format!(
+                    "expected {} chunk ranges, got {}",
+                    data.len() / dimension,
+                    ranges.len()
+                )
+                .into(),
+            ));
+        }
+
+        Ok(Self {
+            data,
+            dimension,
+            ranges,
+        })
+    }
+
+    /// The dimension of every vector stored here.
+    pub fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    /// The number of embedding vectors stored here.
+    pub fn embedding_count(&self) -> usize {
+        self.data.len() / self.dimension
+    }
+
+    /// Iterate over the stored vectors as borrowed slices, in insertion order.
+    pub fn iter(&self) -> impl Iterator<Item = &[f32]> {
+        self.data.chunks(self.dimension)
+    }
+
+    /// The source byte range the `i`th vector's chunk was extracted from.
+    pub fn range(&self, i: usize) -> Option<Range<usize>> {
+        self.ranges.get(i).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_single_round_trips() {
+        let embeddings = Embeddings::from_single(vec![1.0, 2.0, 3.0], 0..10).unwrap();
+        assert_eq!(embeddings.embedding_count(), 1);
+        assert_eq!(embeddings.iter().next(), Some([1.0, 2.0, 3.0].as_slice()));
+    }
+
+    #[test]
+    fn from_single_rejects_empty_vec() {
+        assert!(Embeddings::from_single(vec![], 0..0).is_err());
+    }
+
+    #[test]
+    fn from_inner_rejects_misaligned_data() {
+        assert!(Embeddings::from_inner(vec![1.0, 2.0, 3.0], 2, vec![0..1]).is_err());
+    }
+
+    #[test]
+    fn from_inner_rejects_mismatched_range_count() {
+        assert!(Embeddings::from_inner(vec![1.0, 2.0, 3.0, 4.0], 2, vec![0..1]).is_err());
+    }
+
+    #[test]
+    fn iter_yields_each_vector() {
+        let embeddings =
+            Embeddings::from_inner(vec![1.0, 2.0, 3.0, 4.0], 2, vec![0..1, 1..2]).unwrap();
+        let vecs: Vec<_> = embeddings.iter().collect();
+        assert_eq!(vecs, vec![[1.0, 2.0].as_slice(), [3.0, 4.0].as_slice()]);
+    }
+}
@@ -0,0 +1,10 @@
+//! Generating and working with vector embeddings of `Embeddable` documents.
+
+pub mod builder;
+pub mod embeddings;
+pub mod retry;
+pub mod text_splitter;
+
+pub use builder::{EmbeddingsBuilder, FailedChunk};
+pub use embeddings::Embeddings;
+pub use retry::RetryPolicy;
@@ -0,0 +1,65 @@
+//! Retry/backoff policy used by [`EmbeddingsBuilder::build`](crate::embeddings::EmbeddingsBuilder::build)
+//! (and [`build_partial`](crate::embeddings::EmbeddingsBuilder::build_partial)) when a
+//! chunk's embedding request fails with a transient or rate-limit error.
+
+use std::time::Duration;
+
+use crate::embeddings::EmbeddingError;
+
+/// How many times, and how long to wait between attempts, to retry a chunk whose
+/// embedding request failed with a retryable [`EmbeddingError`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RetryPolicy {
+    max_retries: usize,
+    initial_backoff: Duration,
+    backoff_multiplier: f64,
+}
+
+impl RetryPolicy {
+    /// Retry up to `max_retries` times, starting at a 500ms backoff that doubles on each
+    /// subsequent attempt.
+    pub fn new(max_retries: usize) -> Self {
+        Self {
+            max_retries,
+            initial_backoff: Duration::from_millis(500),
+            backoff_multiplier: 2.0,
+        }
+    }
+
+    /// Set the backoff before the first retry. Defaults to 500ms.
+    pub fn initial_backoff(mut self, backoff: Duration) -> Self {
+        self.initial_backoff = backoff;
+        self
+    }
+
+    /// Set the multiplier applied to the backoff after each retry. Defaults to `2.0`.
+    pub fn backoff_multiplier(mut self, multiplier: f64) -> Self {
+        self.backoff_multiplier = multiplier;
+        self
+    }
+
+    pub fn max_retries(&self) -> usize {
+        self.max_retries
+    }
+
+    /// The backoff to wait before attempt number `attempt` (0-indexed, so `attempt == 0`
+    /// is the wait before the first retry).
+    pub fn backoff_for(&self, attempt: usize) -> Duration {
+        self.initial_backoff
+            .mul_f64(self.backoff_multiplier.powi(attempt as i32))
+    }
+
+    /// Whether a failed chunk is worth retrying at all. Errors that stem from the request
+    /// itself being malformed (e.g. a dimension mismatch we raised locally) will never
+    /// succeed on retry, so only transient/provider errors are retryable.
+    pub fn is_retryable(&self, error: &EmbeddingError) -> bool {
+        !matches!(error, EmbeddingError::DocumentError(_))
+    }
+}
+
+impl Default for RetryPolicy {
+    /// No retries: a single attempt, matching the builder's historical behavior.
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
@@ -0,0 +1,305 @@
+//! Token-aware text chunking used by [`EmbeddingsBuilder`](crate::embeddings::EmbeddingsBuilder)
+//! to keep embed targets within a model's context window.
+//!
+//! [`TextSplitter`] walks a string accumulating pieces until adding the next piece would
+//! push the running chunk past its token budget, then emits the chunk and starts a new
+//! one (optionally re-seeding it with a small overlap so context isn't lost across the
+//! boundary). Splitting prefers natural boundaries — paragraphs, then sentences, then
+//! whitespace — and only falls back to a hard split when a single piece is itself larger
+//! than the budget.
+
+use std::ops::Range;
+
+/// A chunk produced by [`TextSplitter::split`], paired with the byte range in the
+/// original text it was extracted from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextChunk {
+    pub text: String,
+    pub range: Range<usize>,
+}
+
+/// Splits text into chunks that stay within a token budget.
+///
+/// Token counts are approximated with a whitespace-based heuristic rather than a
+/// model-specific tokenizer, which keeps the splitter dependency-free at the cost of
+/// being a conservative estimate for byte-pair-encoded models.
+#[derive(Debug, Clone, Copy)]
+pub struct TextSplitter {
+    /// Maximum number of (estimated) tokens allowed in a single chunk.
+    max_tokens: usize,
+    /// Number of trailing tokens from a chunk to re-seed the next chunk with, so a
+    /// downstream reader doesn't lose context at the boundary.
+    overlap_tokens: usize,
+}
+
+impl TextSplitter {
+    /// Create a splitter that keeps chunks under `max_tokens` (estimated) tokens.
+    pub fn new(max_tokens: usize) -> Self {
+        Self {
+            max_tokens: max_tokens.max(1),
+            overlap_tokens: 0,
+        }
+    }
+
+    /// Re-seed each chunk (after the first) with the trailing `overlap_tokens` tokens of
+    /// the previous chunk. The overlap is left out of a chunk whose first piece would not
+    /// fit in `max_tokens` alongside it.
+    pub fn with_overlap(mut self, overlap_tokens: usize) -> Self {
+        self.overlap_tokens = overlap_tokens;
+        self
+    }
+
+    /// Split `text` into token-bounded chunks, each tagged with the byte range in `text`
+    /// it was extracted from.
+    pub fn split(&self, text: &str) -> Vec<TextChunk> {
+        if text.is_empty() {
+            // Still emit a (empty) chunk: an embed target that happens to be empty
+            // (e.g. an optional field with no value) should produce one embedding, not
+            // zero — dropping it here would silently drop the whole document downstream
+            // if it were the document's only embed target.
+            return vec![TextChunk {
+                text: String::new(),
+                range: 0..0,
+            }];
+        }
+
+        if estimate_tokens(text) <= self.max_tokens {
+            return vec![TextChunk {
+                text: text.to_string(),
+                range: 0..text.len(),
+            }];
+        }
+
+        let leaves = leaf_pieces(text, 0..text.len(), self.max_tokens);
+        self.pack(text, leaves)
+    }
+
+    /// Greedily merge adjacent leaf pieces into chunks no larger than `max_tokens`,
+    /// re-seeding each new chunk with the tail of the previous one when that tail and the
+    /// chunk's first piece fit in the budget together.
+    fn pack(&self, text: &str, leaves: Vec<Range<usize>>) -> Vec<TextChunk> {
+        let mut chunks = Vec::new();
+        let mut start = None;
+        let mut end = 0;
+        let mut tokens = 0;
+
+        for piece in leaves {
+            let piece_tokens = estimate_tokens(&text[piece.clone()]);
+
+            if start.is_some() && tokens + piece_tokens > self.max_tokens {
+                chunks.push(TextChunk {
+                    text: text[start.unwrap()..end].to_string(),
+                    range: start.unwrap()..end,
+                });
+
+                let overlap_start = overlap_start(text, start.unwrap(), end, self.overlap_tokens);
+                start = Some(overlap_start);
+                tokens = estimate_tokens(&text[overlap_start..end]);
+
+                // Drop the overlap when it leaves no room for this piece within the budget.
+                if tokens + piece_tokens > self.max_tokens {
+                    start = None;
+                }
+            }
+
+            if start.is_none() {
+                start = Some(piece.start);
+                tokens = 0;
+            }
+
+            end = piece.end;
+            tokens += piece_tokens;
+        }
+
+        if let Some(start) = start {
+            chunks.push(TextChunk {
+                text: text[start..end].to_string(),
+                range: start..end,
+            });
+        }
+
+        chunks
+    }
+}
+
+/// Rough, provider-agnostic token estimate: ~1 token per 4 bytes of English text, or one
+/// per whitespace-separated word, whichever is larger.
+fn estimate_tokens(text: &str) -> usize {
+    text.split_whitespace().count().max(text.len() / 4).max(1)
+}
+
+/// Byte offset, no earlier than `start`, that keeps roughly the last `overlap_tokens`
+/// tokens of `text[start..end]`.
+fn overlap_start(text: &str, start: usize, end: usize, overlap_tokens: usize) -> usize {
+    if overlap_tokens == 0 {
+        return end;
+    }
+
+    let slice = &text[start..end];
+    let words: Vec<_> = slice.split_whitespace().collect();
+    if words.len() <= overlap_tokens {
+        return start;
+    }
+
+    let kept = &words[words.len() - overlap_tokens..];
+    let offset_in_slice = slice.rfind(kept[0]).unwrap_or(0);
+    start + offset_in_slice
+}
+
+/// Recursively break the piece `text[range]` down to the first boundary kind (paragraph,
+/// sentence, whitespace, byte) that keeps every resulting piece under `max_tokens`.
+fn leaf_pieces(text: &str, range: Range<usize>, max_tokens: usize) -> Vec<Range<usize>> {
+    split_on(text, "\n\n", range.clone(), max_tokens)
+        .unwrap_or_else(|| split_on_any(text, &[". ", "! ", "? "], range, max_tokens))
+}
+
+/// Split the piece `text[range]` on `sep`, recursing into any resulting sub-piece that's
+/// still over `max_tokens`. Returns `None` (rather than looping forever) when `sep` occurs
+/// in the piece but doesn't actually divide it into more than one sub-piece — e.g. a
+/// trailing paragraph break right at the end of `range`.
+fn split_on(text: &str, sep: &str, range: Range<usize>, max_tokens: usize) -> Option<Vec<Range<usize>>> {
+    let body = &text[range.clone()];
+    if !body.contains(sep) {
+        return None;
+    }
+
+    let pieces = split_keeping_separator(body, sep);
+    if pieces.len() <= 1 {
+        return None;
+    }
+
+    Some(
+        pieces
+            .into_iter()
+            .flat_map(|local_range| {
+                let piece = (range.start + local_range.start)..(range.start + local_range.end);
+                if estimate_tokens(&text[piece.clone()]) <= max_tokens {
+                    vec![piece]
+                } else {
+                    leaf_pieces(text, piece, max_tokens)
+                }
+            })
+            .collect(),
+    )
+}
+
+fn split_on_any(text: &str, seps: &[&str], range: Range<usize>, max_tokens: usize) -> Vec<Range<usize>> {
+    for sep in seps {
+        if let Some(pieces) = split_on(text, sep, range.clone(), max_tokens) {
+            return pieces;
+        }
+    }
+
+    split_on(text, " ", range.clone(), max_tokens).unwrap_or_else(|| hard_split(text, range, max_tokens))
+}
+
+/// Split `body` into ranges that each end right after an occurrence of `sep` (so the
+/// separator stays attached to the preceding piece, matching how a reader would expect
+/// paragraphs/sentences to read back).
+fn split_keeping_separator(body: &str, sep: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut start = 0;
+
+    while let Some(found) = body[start..].find(sep) {
+        let end = start + found + sep.len();
+        ranges.push(start..end);
+        start = end;
+    }
+
+    if start < body.len() {
+        ranges.push(start..body.len());
+    }
+
+    ranges
+}
+
+/// Last-resort split on raw char boundaries, for a single "word" that alone exceeds the
+/// token budget (e.g. a long URL or hash).
+fn hard_split(text: &str, range: Range<usize>, max_tokens: usize) -> Vec<Range<usize>> {
+    let body = &text[range.clone()];
+    // `estimate_tokens` is ~4 bytes/token, so cut at the byte budget implied by
+    // `max_tokens`, backing off to the nearest earlier char boundary.
+    let byte_budget = (max_tokens * 4).max(1);
+
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    while start < body.len() {
+        let mut end = (start + byte_budget).min(body.len());
+        while !body.is_char_boundary(end) {
+            end -= 1;
+        }
+        ranges.push((range.start + start)..(range.start + end));
+        start = end;
+    }
+
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_text_is_a_single_chunk() {
+        let chunks = TextSplitter::new(100).split("hello world");
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].text, "hello world");
+        assert_eq!(chunks[0].range, 0..11);
+    }
+
+    #[test]
+    fn long_text_is_split_on_paragraphs() {
+        let text = "first paragraph here.\n\nsecond paragraph here.\n\nthird paragraph here.";
+        let chunks = TextSplitter::new(6).split(text);
+
+        assert!(chunks.len() > 1);
+        for chunk in &chunks {
+            assert_eq!(&text[chunk.range.clone()], chunk.text);
+        }
+    }
+
+    #[test]
+    fn overlap_repeats_trailing_tokens() {
+        let text = "one two three four five six seven eight nine ten";
+        let chunks = TextSplitter::new(4).with_overlap(2).split(text);
+
+        assert!(chunks.len() > 1);
+        assert!(chunks[1].text.starts_with("three four") || chunks[1].text.contains("four"));
+    }
+
+    #[test]
+    fn empty_text_yields_one_empty_chunk() {
+        let chunks = TextSplitter::new(10).split("");
+        assert_eq!(chunks, vec![TextChunk { text: String::new(), range: 0..0 }]);
+    }
+
+    #[test]
+    fn oversized_piece_followed_by_more_paragraphs_does_not_overflow() {
+        // Regression test: the middle paragraph alone is too big to fit in any chunk, and
+        // is followed by more paragraph breaks. A previous version of `leaf_pieces`
+        // recursed on `text[offset..]` instead of the oversized piece's own range, so it
+        // re-split the *rest of the document* forever instead of shrinking its input.
+        let long_paragraph = "word ".repeat(50);
+        let text = format!("short one.\n\n{long_paragraph}\n\nshort two.\n\nshort three.");
+
+        let chunks = TextSplitter::new(8).split(&text);
+
+        assert!(chunks.len() > 1);
+        for chunk in &chunks {
+            assert_eq!(&text[chunk.range.clone()], chunk.text);
+        }
+    }
+
+    #[test]
+    fn trailing_separator_does_not_loop_forever() {
+        // The paragraph separator occurs, but only right at the end of the piece, so
+        // splitting on it doesn't actually divide the piece into anything smaller.
+        let text = format!("{}\n\n", "word ".repeat(50));
+        let chunks = TextSplitter::new(8).split(&text);
+
+        assert!(!chunks.is_empty());
+        for chunk in &chunks {
+            assert_eq!(&text[chunk.range.clone()], chunk.text);
+        }
+    }
+}